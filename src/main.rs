@@ -1,16 +1,22 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use wgpu::{Instance, Backends, PowerPreference, Features, Limits};
 use wgpu::{SurfaceConfiguration, PresentMode, TextureUsages};
 use wgpu::{RenderPipelineDescriptor, RequestAdapterOptions, DeviceDescriptor};
 use wgpu::{ShaderModuleDescriptor, ShaderSource, VertexState, PrimitiveState};
 use wgpu::{TextureViewDescriptor, FragmentState, MultisampleState};
-use wgpu::{CommandEncoderDescriptor, RenderPassDescriptor};
-use wgpu::{RenderPassColorAttachment, LoadOp, Operations, Color};
+use wgpu::{LoadOp, Color, RenderPassDescriptor, RenderPassColorAttachment, Operations};
+use wgpu::SurfaceError;
 use wgpu::util::{DeviceExt, BufferInitDescriptor};
-use winit::event::{Event, WindowEvent};
+use winit::event::{Event, WindowEvent, ElementState, VirtualKeyCode};
 use winit::window::WindowBuilder;
 use winit::event_loop::{EventLoop, ControlFlow};
 
+mod render_graph;
+mod overlay;
+use render_graph::{RenderGraph, RenderGraphPassDesc, SlotDesc};
+use overlay::Overlay;
+
 fn main() {
     // Create the logger to use
     env_logger::init();
@@ -20,9 +26,13 @@ fn main() {
 
     // Create a window
     let window = WindowBuilder::new()
-        .with_resizable(false)
+        .with_resizable(true)
         .build(&event_loop).unwrap();
 
+    // Grabbed up front since it's needed both for the swapchain and for
+    // the camera's aspect ratio.
+    let size = window.inner_size();
+
     // Create new instance of WGPU using a first-tier supported backend
     // Eg: Vulkan + Metal + DX12 + Browser WebGPU
     let instance = Instance::new(Backends::PRIMARY);
@@ -35,27 +45,50 @@ fn main() {
     // which rendered images may be presented.
     let surface = unsafe { instance.create_surface(&window) };
 
-    // Get a handle to a physical graphics and/or compute device
+    // Get a handle to a physical graphics and/or compute device. Try
+    // progressively less demanding requests rather than hard-crashing on
+    // the first failure, since a discrete GPU isn't guaranteed to be
+    // present (laptops with only an integrated GPU, headless CI runners,
+    // etc): high-performance first, then low-power, then finally force
+    // the software-rasterizer fallback adapter before giving up.
+    const ADAPTER_TIERS: &[(&str, PowerPreference, bool)] = &[
+        ("high-performance", PowerPreference::HighPerformance, false),
+        ("low-power", PowerPreference::LowPower, false),
+        ("software fallback", PowerPreference::LowPower, true),
+    ];
+
+    let mut adapter_tier = "";
     let adapter = pollster::block_on(async {
-        instance.request_adapter(&RequestAdapterOptions {
-            // Request the high performance graphics adapter, eg. pick the
-            // discrete GPU over the integrated GPU
-            power_preference: PowerPreference::HighPerformance,
+        for &(tier, power_preference, force_fallback_adapter) in ADAPTER_TIERS {
+            let adapter = instance.request_adapter(&RequestAdapterOptions {
+                power_preference,
 
-            // Don't force fallback, we don't want software rendering :D
-            force_fallback_adapter: false,
+                // Force the software backend on the last tier only; the
+                // earlier tiers still want real hardware.
+                force_fallback_adapter,
 
-            // Make sure the adapter we request can render on `surface`
-            compatible_surface: Some(&surface),
-        }).await.expect("Failed to find an appropriate adapter")
+                // Make sure the adapter we request can render on `surface`
+                compatible_surface: Some(&surface),
+            }).await;
+
+            if let Some(adapter) = adapter {
+                adapter_tier = tier;
+                return adapter;
+            }
+        }
+
+        panic!("Failed to find an appropriate adapter at any tier \
+            (high-performance, low-power, or software fallback)");
     });
 
-    // Display renderer information
+    // Display renderer information, including which tier of adapter
+    // request actually succeeded.
     let adapter_info = adapter.get_info();
-    println!("Renderer: {:04x}:{:04x} | {} | {:?} | {:?}",
+    println!("Renderer: {:04x}:{:04x} | {} | {:?} | {:?} | tier: {}",
         adapter_info.vendor, adapter_info.device,
         adapter_info.name,
-        adapter_info.device_type, adapter_info.backend);
+        adapter_info.device_type, adapter_info.backend,
+        adapter_tier);
 
     // Create the logical device and command queue
     let (device, queue) = pollster::block_on(async {
@@ -74,15 +107,89 @@ fn main() {
     });
 
     // Load the shaders from disk
-    let shader = device.create_shader_module(&ShaderModuleDescriptor {
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
         label:  None,
         source: ShaderSource::Wgsl(
             Cow::Borrowed(include_str!("shader.wgsl"))),
     });
+    let blit_shader = device.create_shader_module(ShaderModuleDescriptor {
+        label:  None,
+        source: ShaderSource::Wgsl(
+            Cow::Borrowed(include_str!("blit.wgsl"))),
+    });
+
+    // Get a texture format the surface supports, then strip any
+    // `*UnormSrgb` suffix: the blit shader below does the linear->sRGB
+    // conversion itself, so the swapchain's color space has to be pinned
+    // to non-sRGB or the hardware would re-encode the already-encoded
+    // output on top, producing washed-out, double-gamma'd colors. This is
+    // also what lets the overlay pass draw straight into the swapchain
+    // afterwards without a second, inconsistent encoding step.
+    let swapchain_format = match surface.get_supported_formats(&adapter)[0] {
+        wgpu::TextureFormat::Rgba8UnormSrgb => wgpu::TextureFormat::Rgba8Unorm,
+        wgpu::TextureFormat::Bgra8UnormSrgb => wgpu::TextureFormat::Bgra8Unorm,
+        format => format,
+    };
 
-    // Get the preferred texture format for the swapchain with the surface and
-    // adapter we are using
-    let swapchain_format = surface.get_preferred_format(&adapter).unwrap();
+    // The triangles are drawn into an offscreen texture in a linear format
+    // first; the blit pass below samples it and performs the linear->sRGB
+    // conversion itself, so scene rendering never has to care whether the
+    // swapchain format the surface picked is sRGB or not.
+    let scene_color_format = wgpu::TextureFormat::Rgba8Unorm;
+
+    // A static camera looking down -Z at the origin. The view stays fixed,
+    // but the projection depends on the window's aspect ratio, so it's
+    // recomputed and re-uploaded (see `compute_view_proj` below) whenever
+    // the window resizes, not just once up front.
+    let camera_view = glam::Mat4::look_at_rh(
+        glam::Vec3::new(0.0, 0.5, 2.0), glam::Vec3::ZERO, glam::Vec3::Y);
+    let compute_view_proj = move |size: winit::dpi::PhysicalSize<u32>| {
+        let aspect = size.width as f32 / size.height.max(1) as f32;
+        let proj = glam::Mat4::perspective_rh(45.0f32.to_radians(), aspect, 0.1, 100.0);
+        (proj * camera_view).to_cols_array()
+    };
+    let view_proj = compute_view_proj(size);
+
+    let camera_buffer = device.create_buffer_init(&BufferInitDescriptor {
+        label: None,
+        contents: unsafe {
+            std::slice::from_raw_parts(
+                view_proj.as_ptr() as *const u8,
+                std::mem::size_of_val(&view_proj))
+        },
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let camera_bind_group_layout = device.create_bind_group_layout(
+        &wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        }
+    );
+    let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &camera_bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: camera_buffer.as_entire_binding(),
+        }],
+    });
+    let camera_pipeline_layout = device.create_pipeline_layout(
+        &wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&camera_bind_group_layout],
+            push_constant_ranges: &[],
+        }
+    );
 
     #[repr(C)]
     #[derive(Copy, Clone, Debug)]
@@ -91,12 +198,29 @@ fn main() {
         color: [f32; 3],
     }
 
-    let mut verts = Vec::new();
-    for _ in 0..1_000_000 {
-        verts.push(Vertex { position: [0.0, 0.5, 0.0], color: [1.0, 0.0, 0.0] });
-        verts.push(Vertex { position: [-0.5, -0.5, 0.0], color: [0.0, 1.0, 0.0] });
-        verts.push(Vertex { position: [0.5, -0.5, 0.0], color: [0.0, 0.0, 1.0] });
-    }
+    // The geometry is just the three corners of one triangle, repeated
+    // `TRIANGLE_COUNT` times. When `INDEXED_DRAWING` is set we upload the
+    // three unique vertices once and repeat only a 4-byte index per corner
+    // instead of the whole 24-byte `Vertex`, so flip it to benchmark the
+    // two paths against each other.
+    const TRIANGLE_COUNT: u32 = 1_000_000;
+    const INDEXED_DRAWING: bool = true;
+
+    let unique_verts = [
+        Vertex { position: [0.0, 0.5, 0.0], color: [1.0, 0.0, 0.0] },
+        Vertex { position: [-0.5, -0.5, 0.0], color: [0.0, 1.0, 0.0] },
+        Vertex { position: [0.5, -0.5, 0.0], color: [0.0, 0.0, 1.0] },
+    ];
+
+    let verts: Vec<Vertex> = if INDEXED_DRAWING {
+        unique_verts.to_vec()
+    } else {
+        let mut verts = Vec::new();
+        for _ in 0..TRIANGLE_COUNT {
+            verts.extend_from_slice(&unique_verts);
+        }
+        verts
+    };
 
     // Create a vertex buffer
     let vertex_buffer = device.create_buffer_init(
@@ -111,6 +235,25 @@ fn main() {
         }
     );
 
+    // When indexing, each of the three corners above is repeated
+    // `TRIANGLE_COUNT` times as a `u32` index rather than a full `Vertex`.
+    let index_buffer = INDEXED_DRAWING.then(|| {
+        let indices: Vec<u32> = (0..TRIANGLE_COUNT)
+            .flat_map(|_| [0u32, 1, 2])
+            .collect();
+
+        device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: unsafe {
+                std::slice::from_raw_parts(
+                    indices.as_ptr() as *const u8,
+                    std::mem::size_of_val(indices.as_slice()))
+            },
+            usage: wgpu::BufferUsages::INDEX,
+        })
+    });
+    let index_count = TRIANGLE_COUNT * 3;
+
     // Create the vertex buffer layout, describing the shape of the vertex
     // buffer
     let vbl = wgpu::VertexBufferLayout {
@@ -137,8 +280,9 @@ fn main() {
         // for easy identification.
         label:  None,
 
-        // The layout of bind groups for this pipeline.
-        layout: None,
+        // The layout of bind groups for this pipeline: just the camera's
+        // view-projection uniform, bound at group 0.
+        layout: Some(&camera_pipeline_layout),
 
         // The compiled vertex stage, its entry point, and the input buffers
         // layout.
@@ -165,14 +309,20 @@ fn main() {
             // Name of the function for the entry point
             entry_point: "fs_main",
 
-            // Type of output for the fragment shader (the correct texture
-            // format that our GPU wants)
-            targets: &[swapchain_format.into()],
+            // Type of output for the fragment shader. This targets the
+            // offscreen scene-color texture, not the swapchain directly.
+            targets: &[Some(scene_color_format.into())],
         }),
 
-        // The effect of draw calls on the depth and stencil aspects of the
-        // output target, if any.
-        depth_stencil: None,
+        // Depth-test against the scene's depth buffer so 3D geometry gets
+        // sorted correctly regardless of draw order.
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
 
         // The multi-sampling properties of the pipeline.
         multisample: MultisampleState::default(),
@@ -182,33 +332,188 @@ fn main() {
         multiview: None,
     });
 
-    // Configure the swap buffers
-    let size = window.inner_size();
-    surface.configure(&device, &SurfaceConfiguration {
-        // Usage for the swap chain. In this case, this is currently the only
-        // supported option.
-        usage: TextureUsages::RENDER_ATTACHMENT,
-
-        // Set the preferred texture format for the swap chain to be what the
-        // surface and adapter want.
-        format: surface.get_preferred_format(&adapter).unwrap(),
-
-        // Set the width of the swap chain
-        width: size.width,
-
-        // Set the height of the swap chain
-        height: size.height,
-
-        // The way data is presented to the screen
-        // `Immediate` (no vsync)
-        // `Mailbox`   (no vsync for rendering, but frames synced on vsync)
-        // `Fifo`      (full vsync)
-        present_mode: PresentMode::Immediate,
+    // The blit pass samples the offscreen scene-color texture and writes
+    // it to the swapchain with an in-shader linear->sRGB conversion.
+    let blit_sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+    let blit_bind_group_layout = device.create_bind_group_layout(
+        &wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        }
+    );
+    let blit_pipeline_layout = device.create_pipeline_layout(
+        &wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&blit_bind_group_layout],
+            push_constant_ranges: &[],
+        }
+    );
+    let blit_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&blit_pipeline_layout),
+        vertex: VertexState {
+            module: &blit_shader,
+            entry_point: "vs_main",
+            // The fullscreen triangle's 3 vertices are generated in the
+            // shader from `vertex_index`, so there's no vertex buffer.
+            buffers: &[],
+        },
+        primitive: PrimitiveState::default(),
+        fragment: Some(FragmentState {
+            module: &blit_shader,
+            entry_point: "fs_main",
+            targets: &[Some(swapchain_format.into())],
+        }),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        multiview: None,
     });
 
+    // Build the render graph:
+    //   triangle (-> scene_color) -> blit (scene_color -> swapchain) -> overlay (swapchain -> swapchain)
+    let vertex_count = verts.len() as u32;
+    let mut render_graph = RenderGraph::new();
+    render_graph.add_pass(
+        RenderGraphPassDesc {
+            name: "triangle",
+            inputs: vec![],
+            outputs: vec![("scene_color", SlotDesc::Transient {
+                format: scene_color_format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            }, LoadOp::Clear(Color::BLACK))],
+            depth: Some(("depth", SlotDesc::Transient {
+                format: wgpu::TextureFormat::Depth32Float,
+                usage: TextureUsages::RENDER_ATTACHMENT,
+            }, Operations { load: LoadOp::Clear(1.0), store: true })),
+        },
+        move |encoder, pass_descriptor| {
+            let mut render_pass = encoder.begin_render_pass(pass_descriptor);
+
+            render_pass.set_pipeline(&render_pipeline);
+            render_pass.set_bind_group(0, &camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+
+            if let Some(index_buffer) = &index_buffer {
+                render_pass.set_index_buffer(
+                    index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..index_count, 0, 0..1);
+            } else {
+                render_pass.draw(0..vertex_count, 0..1);
+            }
+        },
+    );
+
+    render_graph.add_raw_pass(
+        RenderGraphPassDesc {
+            name: "blit",
+            inputs: vec!["scene_color"],
+            outputs: vec![("swapchain", SlotDesc::External, LoadOp::Clear(Color::BLACK))],
+            depth: None,
+        },
+        move |device, _queue, encoder, slots, _size| {
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("blit"),
+                layout: &blit_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(
+                            slots.view("scene_color")),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&blit_sampler),
+                    },
+                ],
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("blit"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: slots.view("swapchain"),
+                    resolve_target: None,
+                    ops: Operations { load: LoadOp::Clear(Color::BLACK), store: true },
+                })],
+                depth_stencil_attachment: None,
+            });
+
+            render_pass.set_pipeline(&blit_pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        },
+    );
+
+    // The stats overlay is a raw pass: it loads (rather than clears) the
+    // swapchain so its text composites over what the blit pass just drew.
+    // It's reference-counted so the event loop below can keep updating
+    // its text and recalling its staging belt between frames.
+    let overlay = std::rc::Rc::new(Overlay::new(&device, swapchain_format));
+    render_graph.add_raw_pass(
+        RenderGraphPassDesc {
+            name: "overlay",
+            inputs: vec!["swapchain"],
+            outputs: vec![("swapchain", SlotDesc::External, LoadOp::Load)],
+            depth: None,
+        },
+        {
+            let overlay = std::rc::Rc::clone(&overlay);
+            move |device, queue, encoder, slots, size| {
+                overlay.draw(device, queue, encoder, slots.view("swapchain"), size);
+            }
+        },
+    );
+
+    // Builds a `SurfaceConfiguration` for the given size and present mode
+    // so resize and present-mode changes can reconfigure the surface
+    // without repeating all the fixed fields.
+    //
+    // `Immediate` (no vsync)
+    // `Mailbox`   (no vsync for rendering, but frames synced on vsync)
+    // `Fifo`      (full vsync)
+    let make_surface_config = move |size: winit::dpi::PhysicalSize<u32>, present_mode: PresentMode| {
+        SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format: swapchain_format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+        }
+    };
+
+    let mut size = size;
+    let mut present_mode = PresentMode::Immediate;
+
+    // Configure the swap buffers
+    surface.configure(&device, &make_surface_config(size, present_mode));
+
     // Run the event loop
     let it = std::time::Instant::now();
     let mut frames = 0u64;
+
+    // Text for the overlay is one frame stale: it's built from this
+    // frame's timings after `present`, then drawn at the start of the
+    // *next* frame, since the overlay pass itself runs inside the same
+    // submit it would be reporting on.
+    let mut overlay_text = String::new();
+
     event_loop.run(move |event, _, control_flow| {
         // ControlFlow::Wait pauses the event loop if no events are available
         // to process.  This is ideal for non-game applications that only
@@ -222,69 +527,102 @@ fn main() {
                 // Exit when the user closes the window
                 *control_flow = ControlFlow::Exit;
             }
+            Event::WindowEvent { event: WindowEvent::Resized(new_size), .. } => {
+                size = new_size;
+                if size.width > 0 && size.height > 0 {
+                    surface.configure(&device, &make_surface_config(size, present_mode));
+                    let view_proj = compute_view_proj(size);
+                    queue.write_buffer(&camera_buffer, 0, unsafe {
+                        std::slice::from_raw_parts(
+                            view_proj.as_ptr() as *const u8,
+                            std::mem::size_of_val(&view_proj))
+                    });
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ScaleFactorChanged { new_inner_size, .. }, ..
+            } => {
+                size = *new_inner_size;
+                if size.width > 0 && size.height > 0 {
+                    surface.configure(&device, &make_surface_config(size, present_mode));
+                    let view_proj = compute_view_proj(size);
+                    queue.write_buffer(&camera_buffer, 0, unsafe {
+                        std::slice::from_raw_parts(
+                            view_proj.as_ptr() as *const u8,
+                            std::mem::size_of_val(&view_proj))
+                    });
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } => {
+                // Let the present mode be toggled live so vsync behavior
+                // can be benchmarked without restarting.
+                let requested = match (input.state, input.virtual_keycode) {
+                    (ElementState::Pressed, Some(VirtualKeyCode::Key1)) => Some(PresentMode::Immediate),
+                    (ElementState::Pressed, Some(VirtualKeyCode::Key2)) => Some(PresentMode::Mailbox),
+                    (ElementState::Pressed, Some(VirtualKeyCode::Key3)) => Some(PresentMode::Fifo),
+                    _ => None,
+                };
+
+                if let Some(requested) = requested {
+                    if surface.get_supported_present_modes(&adapter).contains(&requested) {
+                        present_mode = requested;
+                        surface.configure(&device, &make_surface_config(size, present_mode));
+                        println!("Present mode: {:?}", present_mode);
+                    } else {
+                        println!("Present mode {:?} not supported by this surface, ignoring", requested);
+                    }
+                }
+            }
             Event::RedrawRequested(_) => {
-                println!("[{:16.6}] redraw req", it.elapsed().as_secs_f64());
+                // A minimized window reports a 0x0 size; there's no surface
+                // to draw into and `render_graph.execute` would try to
+                // allocate a 0-sized transient texture, so skip the frame.
+                if size.width == 0 || size.height == 0 {
+                    return;
+                }
+
+                let t_redraw = it.elapsed();
 
                 // Redraw the application
-                let frame = surface
-                    .get_current_texture()
-                    .expect("Failed to acquire next swap chain texture");
-                println!("[{:16.6}] got frame", it.elapsed().as_secs_f64());
+                let frame = match surface.get_current_texture() {
+                    Ok(frame) => frame,
+                    Err(SurfaceError::Lost | SurfaceError::Outdated) => {
+                        // Recreate the swap chain and just skip this frame;
+                        // the next `RedrawRequested` will use the fresh one.
+                        surface.configure(&device, &make_surface_config(size, present_mode));
+                        return;
+                    }
+                    Err(e) => panic!("Failed to acquire next swap chain texture: {e}"),
+                };
+                let t_got_frame = it.elapsed();
 
                 // Create a view of the texture used in the frame
                 let view = frame.texture
                     .create_view(&TextureViewDescriptor::default());
-                println!("[{:16.6}] got view", it.elapsed().as_secs_f64());
-
-                // An encoder for a series of GPU operations
-                let mut encoder = device.create_command_encoder(
-                    &CommandEncoderDescriptor::default());
-                println!("[{:16.6}] got encoder", it.elapsed().as_secs_f64());
-
-                {
-                    // Start a render pass
-                    let mut render_pass = encoder.begin_render_pass(
-                        &RenderPassDescriptor {
-                            label: None,
-                            color_attachments: &[RenderPassColorAttachment {
-                                view: &view,
-                                resolve_target: None,
-                                ops: Operations {
-                                    load: LoadOp::Clear(Color::BLACK),
-                                    store: true,
-                                },
-                            }],
-                            depth_stencil_attachment: None,
-                        });
-                    println!("[{:16.6}] render pass",
-                        it.elapsed().as_secs_f64());
-
-                    // Pick the pipeline to use for rendering
-                    render_pass.set_pipeline(&render_pipeline);
-                    println!("[{:16.6}] set pipeline",
-                        it.elapsed().as_secs_f64());
-
-                    // Set the vertex buffer
-                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                    println!("[{:16.6}] set vertex buffer",
-                        it.elapsed().as_secs_f64());
-
-                    // Draw!
-                    render_pass.draw(0..verts.len() as u32, 0..1);
-                    println!("[{:16.6}] drew",
-                        it.elapsed().as_secs_f64());
-                }
-
-                // Finalize the encoder and submit the buffer for execution
-                queue.submit(Some(encoder.finish()));
-                println!("[{:16.6}] submit", it.elapsed().as_secs_f64());
+                let t_got_view = it.elapsed();
+
+                // Execute the render graph. It builds its own encoder,
+                // records every registered pass (including the overlay) in
+                // dependency order, and submits once.
+                overlay.set_text(overlay_text.clone());
+                let mut external = HashMap::new();
+                external.insert("swapchain", &view);
+                render_graph.execute(&device, &queue,
+                    (size.width, size.height), &external);
+                overlay.recall();
+                let t_executed = it.elapsed();
 
                 frame.present();
-                println!("[{:16.6}] present", it.elapsed().as_secs_f64());
+                let t_present = it.elapsed();
 
                 frames += 1;
-                println!("Frame {} | {}",
-                    frames, frames as f64 / it.elapsed().as_secs_f64());
+                overlay_text = format!(
+                    "FPS: {:.1}\nFrame: {}\nacquire: {:.3}ms  view: {:.3}ms  graph: {:.3}ms  present: {:.3}ms",
+                    frames as f64 / it.elapsed().as_secs_f64(), frames,
+                    (t_got_frame - t_redraw).as_secs_f64() * 1000.0,
+                    (t_got_view - t_got_frame).as_secs_f64() * 1000.0,
+                    (t_executed - t_got_view).as_secs_f64() * 1000.0,
+                    (t_present - t_executed).as_secs_f64() * 1000.0);
             },
             Event::MainEventsCleared => {
                 window.request_redraw();
@@ -0,0 +1,78 @@
+//! An on-screen stats overlay (FPS, frame number, per-stage timings).
+//!
+//! Drawn directly into the swapchain frame instead of being printed with
+//! `println!` every frame, since printing that often perturbs the very
+//! timings being measured. The text changes every frame, so glyph quads
+//! are streamed in through a [`StagingBelt`] rather than recreating a
+//! buffer each time: uploads go through `draw_queued` (which internally
+//! calls `write_buffer`), `finish()` runs before the frame's `queue.submit`,
+//! and `recall()` runs once the GPU is done with that submission.
+
+use std::cell::RefCell;
+use wgpu::util::StagingBelt;
+use wgpu_glyph::{ab_glyph, GlyphBrush, GlyphBrushBuilder, Section, Text};
+
+pub struct Overlay {
+    glyph_brush: RefCell<GlyphBrush<()>>,
+    staging_belt: RefCell<StagingBelt>,
+    pending_text: RefCell<String>,
+}
+
+impl Overlay {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let font = ab_glyph::FontArc::try_from_slice(
+            include_bytes!("../assets/DejaVuSansMono.ttf"))
+            .expect("failed to parse overlay font");
+
+        Self {
+            glyph_brush: RefCell::new(GlyphBrushBuilder::using_font(font).build(device, format)),
+            // A handful of short stats lines comfortably fits in a 1 KiB
+            // chunk; the belt grows on demand if that's ever not enough.
+            staging_belt: RefCell::new(StagingBelt::new(1024)),
+            pending_text: RefCell::new(String::new()),
+        }
+    }
+
+    /// Replaces the text drawn on the next call to `draw`.
+    pub fn set_text(&self, text: String) {
+        *self.pending_text.borrow_mut() = text;
+    }
+
+    /// Queues and draws the current text into `view` as a second render
+    /// pass on `encoder`, loading rather than clearing so it composites
+    /// over whatever was already drawn this frame.
+    pub fn draw(
+        &self,
+        device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        size: (u32, u32),
+    ) {
+        let mut glyph_brush = self.glyph_brush.borrow_mut();
+        let mut staging_belt = self.staging_belt.borrow_mut();
+
+        glyph_brush.queue(Section {
+            screen_position: (8.0, 8.0),
+            text: vec![Text::new(&self.pending_text.borrow())
+                .with_color([1.0, 1.0, 1.0, 1.0])
+                .with_scale(18.0)],
+            ..Section::default()
+        });
+
+        glyph_brush
+            .draw_queued(device, &mut staging_belt, encoder, view, size.0, size.1)
+            .expect("failed to draw overlay text");
+
+        // Submission happens after every pass in the render graph has been
+        // recorded; `recall` is the caller's job once that submit lands.
+        staging_belt.finish();
+    }
+
+    /// Reclaims staging belt chunks the GPU has finished consuming. Must
+    /// be called after the `CommandEncoder` passed to `draw` has been
+    /// submitted.
+    pub fn recall(&self) {
+        self.staging_belt.borrow_mut().recall();
+    }
+}
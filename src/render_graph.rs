@@ -0,0 +1,285 @@
+//! A small render graph used to sequence GPU passes for a single frame.
+//!
+//! Instead of hand-ordering `begin_render_pass` calls in `main`, passes are
+//! registered once via [`RenderGraph::add_pass`] (or [`RenderGraph::add_raw_pass`]
+//! for passes that manage their own render pass, like a glyph renderer)
+//! with a [`RenderGraphPassDesc`] describing the named slots they read
+//! from and write to. [`RenderGraph::execute`] resolves a valid execution
+//! order from those slot dependencies, allocates any transient textures
+//! the passes need, and records all of them into a single `CommandEncoder`
+//! before submitting once.
+
+use std::collections::HashMap;
+use wgpu::{Device, Queue, TextureView, TextureFormat, TextureUsages};
+use wgpu::{CommandEncoder, CommandEncoderDescriptor};
+use wgpu::{RenderPassDescriptor, RenderPassColorAttachment, RenderPassDepthStencilAttachment};
+use wgpu::{LoadOp, Operations, Color};
+
+/// Identifies a named slot (a texture, in the current graph) that a pass
+/// either produces or consumes. Producer and consumer passes are wired
+/// together by matching on this name.
+pub type SlotId = &'static str;
+
+/// Describes how the resource backing a slot comes into existence.
+///
+/// Only textures are modeled today — there's no `Buffer` variant, so a
+/// pass that wants to hand a buffer (vertex data, a compute readback, ...)
+/// to a later pass can't be wired through the graph and has to be threaded
+/// in by hand, same as before this type existed. Worth lifting if a pass
+/// that produces one shows up.
+pub enum SlotDesc {
+    /// Bound externally before `execute` runs, e.g. the swapchain view.
+    /// The graph never allocates these.
+    External,
+
+    /// Allocated by the graph for the duration of a single `execute` call,
+    /// sized to match the frame's render target.
+    Transient { format: TextureFormat, usage: TextureUsages },
+}
+
+/// Declares the slots a single pass reads from and writes to, and the
+/// load op each output should use. The graph derives execution order by
+/// matching each input name against the pass that *first* declared it as
+/// an output; a later pass may still declare the same slot as an output
+/// (typically with `LoadOp::Load`) to composite on top of it.
+pub struct RenderGraphPassDesc {
+    pub name: &'static str,
+    pub inputs: Vec<SlotId>,
+    pub outputs: Vec<(SlotId, SlotDesc, LoadOp<Color>)>,
+
+    /// An optional depth/stencil slot this pass writes to. Like a color
+    /// output, it's matched against inputs declaring the same slot name.
+    pub depth: Option<(SlotId, SlotDesc, Operations<f32>)>,
+}
+
+/// Read-only access to this frame's resolved slot views, handed to raw
+/// passes that need to look up a view by name themselves.
+pub struct ResolvedSlots<'a> {
+    transient: &'a HashMap<SlotId, (wgpu::Texture, TextureView)>,
+    external: &'a HashMap<SlotId, &'a TextureView>,
+}
+
+impl<'a> ResolvedSlots<'a> {
+    pub fn view(&self, slot: SlotId) -> &TextureView {
+        if let Some((_, view)) = self.transient.get(slot) {
+            view
+        } else {
+            self.external.get(slot)
+                .unwrap_or_else(|| panic!("render graph: no resource bound for slot '{slot}'"))
+        }
+    }
+}
+
+/// The record callback a color-attachment pass runs. It's handed the
+/// `CommandEncoder` plus an already-resolved `RenderPassDescriptor` (color
+/// and depth attachments wired up to match `desc.outputs`/`desc.depth`) and
+/// opens the `RenderPass` itself, rather than receiving an already-open one:
+/// a `RenderPass<'rp>` passed in from the outside would tie any resource a
+/// closure captures (a pipeline, a bind group) to an external, unbounded
+/// lifetime that a boxed closure can't satisfy. Opening it locally keeps the
+/// borrow scoped to the call, like [`RawRecordFn`] already does.
+type RecordFn = Box<dyn Fn(&mut CommandEncoder, &RenderPassDescriptor)>;
+
+/// The record callback for a raw pass: one that needs the `CommandEncoder`
+/// itself rather than a pre-opened `RenderPass`, because the library it
+/// wraps begins its own pass internally (a glyph brush, for example).
+type RawRecordFn = Box<dyn Fn(&Device, &Queue, &mut CommandEncoder, &ResolvedSlots, (u32, u32))>;
+
+enum Record {
+    Pass(RecordFn),
+    Raw(RawRecordFn),
+}
+
+struct RegisteredPass {
+    desc: RenderGraphPassDesc,
+    record: Record,
+}
+
+/// A collection of passes and the slot dependencies between them.
+#[derive(Default)]
+pub struct RenderGraph {
+    passes: Vec<RegisteredPass>,
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self { passes: Vec::new() }
+    }
+
+    /// Registers a pass. `record` is invoked once per `execute` call with
+    /// the shared `CommandEncoder` and a `RenderPassDescriptor` whose color
+    /// and depth attachments have already been resolved to match
+    /// `desc.outputs`/`desc.depth`; it should open its own `RenderPass` from
+    /// them (typically the first thing it does).
+    pub fn add_pass(
+        &mut self,
+        desc: RenderGraphPassDesc,
+        record: impl Fn(&mut CommandEncoder, &RenderPassDescriptor) + 'static,
+    ) {
+        self.passes.push(RegisteredPass { desc, record: Record::Pass(Box::new(record)) });
+    }
+
+    /// Registers a raw pass: `record` gets the shared `CommandEncoder`
+    /// directly (plus a [`ResolvedSlots`] to look up its declared slots'
+    /// views) instead of a pre-opened `RenderPass`, for libraries that
+    /// open their own render pass.
+    pub fn add_raw_pass(
+        &mut self,
+        desc: RenderGraphPassDesc,
+        record: impl Fn(&Device, &Queue, &mut CommandEncoder, &ResolvedSlots, (u32, u32)) + 'static,
+    ) {
+        self.passes.push(RegisteredPass { desc, record: Record::Raw(Box::new(record)) });
+    }
+
+    /// Resolves a linear execution order such that every pass runs after
+    /// the producers of its input slots.
+    fn topo_order(&self) -> Vec<usize> {
+        // Map each output slot name to the index of the pass that *first*
+        // produces it, so a later pass re-declaring the same slot (e.g. to
+        // `Load` and composite over it) still depends on the original
+        // producer rather than on itself.
+        let mut producer = HashMap::new();
+        for (i, pass) in self.passes.iter().enumerate() {
+            for (slot, _, _) in &pass.desc.outputs {
+                producer.entry(*slot).or_insert(i);
+            }
+            if let Some((slot, _, _)) = &pass.desc.depth {
+                producer.entry(*slot).or_insert(i);
+            }
+        }
+
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Unvisited,
+            /// On the current DFS path; seeing this again means a cycle.
+            InProgress,
+            Done,
+        }
+
+        let mut marks = vec![Mark::Unvisited; self.passes.len()];
+        let mut order = Vec::with_capacity(self.passes.len());
+
+        fn visit(
+            i: usize,
+            passes: &[RegisteredPass],
+            producer: &HashMap<SlotId, usize>,
+            marks: &mut Vec<Mark>,
+            order: &mut Vec<usize>,
+        ) {
+            match marks[i] {
+                Mark::Done => return,
+                Mark::InProgress => panic!(
+                    "render graph: slot dependency cycle detected at pass '{}'",
+                    passes[i].desc.name),
+                Mark::Unvisited => {}
+            }
+            marks[i] = Mark::InProgress;
+
+            for input in &passes[i].desc.inputs {
+                if let Some(&dep) = producer.get(input) {
+                    visit(dep, passes, producer, marks, order);
+                }
+            }
+
+            marks[i] = Mark::Done;
+            order.push(i);
+        }
+
+        for i in 0..self.passes.len() {
+            visit(i, &self.passes, &producer, &mut marks, &mut order);
+        }
+
+        order
+    }
+
+    /// Allocates transient slots, records every pass into one encoder in
+    /// dependency order, and submits once.
+    ///
+    /// `size` is used to size transient textures and `external` supplies
+    /// the views for any [`SlotDesc::External`] slots (e.g. the current
+    /// swapchain texture view).
+    pub fn execute(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        size: (u32, u32),
+        external: &HashMap<SlotId, &TextureView>,
+    ) {
+        // A 0-sized frame (e.g. a minimized window) can't back a transient
+        // texture; wgpu validation panics on a zero dimension, so just skip
+        // the frame rather than letting that surface here.
+        if size.0 == 0 || size.1 == 0 {
+            return;
+        }
+
+        // Allocate transient textures up front so every pass can borrow its
+        // view for the lifetime of this call.
+        let mut transient = HashMap::new();
+        let mut alloc_transient = |slot: SlotId, desc: &SlotDesc, label: &'static str| {
+            if let SlotDesc::Transient { format, usage } = desc {
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width: size.0,
+                        height: size.1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: *format,
+                    usage: *usage,
+                });
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                transient.insert(slot, (texture, view));
+            }
+        };
+        for pass in &self.passes {
+            for (slot, desc, _) in &pass.desc.outputs {
+                alloc_transient(slot, desc, pass.desc.name);
+            }
+            if let Some((slot, desc, _)) = &pass.desc.depth {
+                alloc_transient(slot, desc, pass.desc.name);
+            }
+        }
+
+        let resolved = ResolvedSlots { transient: &transient, external };
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+
+        for i in self.topo_order() {
+            let pass = &self.passes[i];
+
+            match &pass.record {
+                Record::Pass(record) => {
+                    let color_attachments: Vec<_> = pass.desc.outputs.iter()
+                        .map(|(slot, _, load)| Some(RenderPassColorAttachment {
+                            view: resolved.view(slot),
+                            resolve_target: None,
+                            ops: Operations { load: *load, store: true },
+                        }))
+                        .collect();
+
+                    let depth_stencil_attachment = pass.desc.depth.as_ref()
+                        .map(|(slot, _, ops)| RenderPassDepthStencilAttachment {
+                            view: resolved.view(slot),
+                            depth_ops: Some(*ops),
+                            stencil_ops: None,
+                        });
+
+                    let pass_descriptor = RenderPassDescriptor {
+                        label: Some(pass.desc.name),
+                        color_attachments: &color_attachments,
+                        depth_stencil_attachment,
+                    };
+
+                    record(&mut encoder, &pass_descriptor);
+                }
+                Record::Raw(record) => {
+                    record(device, queue, &mut encoder, &resolved, size);
+                }
+            }
+        }
+
+        queue.submit(Some(encoder.finish()));
+    }
+}